@@ -1,141 +1,617 @@
-use aeco_patch_config::{error::PatchConfigError, generate_config};
+use aeco_patch_config::{
+    error::PatchConfigError,
+    generate_config,
+    preview::{list_patch_entries, PatchEntry},
+};
 use eframe::egui;
 use eframe::epaint::Vec2;
-use rfd::FileDialog;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::{RecursiveMode, Watcher};
+use rfd::FileHandle;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
 use std::{sync::mpsc, thread};
 
-/// Messages which the worker thread (for generating configs) can send back to
-/// the GUI about the result of the operation.
+/// How long to wait after the last filesystem event before re-running
+/// `generate_config`, so a burst of saves only triggers one regeneration.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Messages which a config generation worker thread can send back to the GUI
+/// about the progress or result of its job.
 enum MessageToGUI {
+    /// The worker has made progress; `fraction` is in the range `0.0..=1.0`.
+    Progress {
+        fraction: f32,
+        message: String,
+    },
     Complete,
     Error(PatchConfigError),
+    Cancelled,
 }
 
-struct PatchConfigApp {
+/// How many finished jobs to keep around for the user to glance back at
+/// before they're pruned from the queue; bounds memory and the rendered
+/// "job-queue" list during a long watch session that regenerates often.
+const MAX_FINISHED_JOBS: usize = 5;
+
+/// A single config generation task tracked by the [`JobQueue`].
+struct Job {
+    id: u64,
+    output_dir: PathBuf,
+    status: String,
+    progress: f32,
+    finished: bool,
+    cancel: Arc<AtomicBool>,
+    rx: Receiver<MessageToGUI>,
+}
+
+/// Tracks the config generation jobs that are still running, plus the most
+/// recent [`MAX_FINISHED_JOBS`] finished ones, so multiple runs can be in
+/// flight and reported on at once.
+#[derive(Default)]
+struct JobQueue {
+    jobs: Vec<Job>,
+    next_id: u64,
+}
+
+impl JobQueue {
+    /// Whether a still-running job already targets `output_dir`. Callers
+    /// should skip starting another job for the same output rather than
+    /// racing two workers writing into it concurrently.
+    fn is_running_for(&self, output_dir: &Path) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| !job.finished && job.output_dir == output_dir)
+    }
+
+    /// Registers a new job with the queue and returns its cancel flag and the
+    /// sending half of its progress channel, for the caller to hand off to
+    /// the worker thread.
+    fn start_job(&mut self, output_dir: PathBuf) -> (Sender<MessageToGUI>, Arc<AtomicBool>) {
+        let (tx, rx) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.jobs.push(Job {
+            id,
+            output_dir,
+            status: "Working...".to_string(),
+            progress: 0.0,
+            finished: false,
+            cancel: cancel.clone(),
+            rx,
+        });
+
+        (tx, cancel)
+    }
+
+    /// Polls every unfinished job's channel, updates its reported status and
+    /// progress, and prunes old finished jobs down to [`MAX_FINISHED_JOBS`].
+    fn poll(&mut self) {
+        for job in &mut self.jobs {
+            if job.finished {
+                continue;
+            }
+
+            loop {
+                let message = match job.rx.try_recv() {
+                    Ok(message) => message,
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        job.status = "The worker channel has disconnected.".to_string();
+                        job.finished = true;
+                        break;
+                    }
+                };
+
+                match message {
+                    MessageToGUI::Progress { fraction, message } => {
+                        job.progress = fraction;
+                        job.status = message;
+                    }
+                    MessageToGUI::Complete => {
+                        job.progress = 1.0;
+                        job.status = "Finished!".to_string();
+                        job.finished = true;
+                    }
+                    MessageToGUI::Error(why) => {
+                        job.status = format!("Failed to generate output: {why}");
+                        job.finished = true;
+                    }
+                    MessageToGUI::Cancelled => {
+                        job.status = "Cancelled.".to_string();
+                        job.finished = true;
+                    }
+                }
+            }
+        }
+
+        let mut excess = self
+            .jobs
+            .iter()
+            .filter(|job| job.finished)
+            .count()
+            .saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|job| {
+            if job.finished && excess > 0 {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// Outcome of attempting to start a config generation job, so callers can
+/// decide whether to retry later (e.g. once a job that's in the way
+/// finishes) rather than just dropping the request.
+enum QueueOutcome {
+    Started,
+    Busy,
+    Rejected,
+}
+
+/// A folder picker dialog which is awaited on a background thread so the GUI
+/// thread never blocks on the native file picker.
+struct PendingFolderDialog {
+    rx: Receiver<Option<PathBuf>>,
+}
+
+impl PendingFolderDialog {
+    /// Spawns the native folder picker on a background thread and returns a
+    /// handle that can be polled for the result.
+    fn spawn(ctx: egui::Context) -> Self {
+        let (tx, rx) = channel();
+
+        thread::spawn(move || {
+            let picked = pollster::block_on(async {
+                rfd::AsyncFileDialog::new()
+                    .pick_folder()
+                    .await
+                    .map(FileHandle::into)
+            });
+
+            if tx.send(picked).is_err() {
+                eprintln!("Could not send picked folder back to GUI: receiver dropped");
+            }
+
+            // Wake the GUI so it notices the dialog has finished even if the
+            // user hasn't moved the mouse since picking a folder.
+            ctx.request_repaint();
+        });
+
+        Self { rx }
+    }
+
+    /// Returns `Some(path)` once the dialog has completed (`path` is `None`
+    /// if the user cancelled), or `None` while it is still in-flight.
+    fn poll(&self) -> Option<Option<PathBuf>> {
+        match self.rx.try_recv() {
+            Ok(path) => Some(path),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+/// Watches a Patch Folder for create/modify/delete events and pings back
+/// once per debounced burst of activity.
+struct FileWatcher {
+    // Kept alive for as long as the watcher should keep running; dropping it
+    // stops the underlying OS watch.
+    _watcher: notify::RecommendedWatcher,
+    watched_folder: String,
+    rx: Receiver<()>,
+}
+
+impl FileWatcher {
+    /// Starts watching `folder`, debouncing bursts of events on a background
+    /// thread and waking the GUI via `ctx` each time a debounced change is
+    /// ready to be picked up.
+    fn new(folder: &str, ctx: egui::Context) -> notify::Result<Self> {
+        let (raw_tx, raw_rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = notify::recommended_watcher(raw_tx)?;
+        watcher.watch(Path::new(folder), RecursiveMode::Recursive)?;
+
+        let (tx, rx) = channel();
+
+        thread::spawn(move || {
+            // Block for the first event of a burst, then keep draining the
+            // channel until it goes quiet for `WATCH_DEBOUNCE` before
+            // notifying the GUI.
+            while raw_rx.recv().is_ok() {
+                while raw_rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+                if tx.send(()).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+            watched_folder: folder.to_string(),
+            rx,
+        })
+    }
+
+    /// Returns `true` if a debounced change is ready to be acted on.
+    fn poll_changed(&self) -> bool {
+        self.rx.try_iter().count() > 0
+    }
+}
+
+/// Glob patterns that control which files are swept into the generated
+/// config. A pattern starting with `!` excludes matches instead of including
+/// them, so the same list can allow broad patterns like `**/*` while still
+/// carving out editor temp files, `.DS_Store`, and VCS metadata.
+const DEFAULT_GLOB_PATTERNS: &[&str] = &[
+    "**/*",
+    "!**/.git/**",
+    "!**/.DS_Store",
+    "!**/*.tmp",
+    "!**/*~",
+];
+
+/// The compiled form of the glob pattern list, threaded through
+/// `generate_config` to decide which files get swept into the patch.
+struct PatchFileFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl PatchFileFilter {
+    /// Compiles `patterns`, splitting `!`-prefixed entries into the exclude
+    /// set. Invalid patterns are skipped; their messages are returned in the
+    /// second slot so the caller can surface them to the user rather than
+    /// have them vanish into a console no one is attached to.
+    fn compile(patterns: &[String]) -> (Self, Vec<String>) {
+        let mut include_builder = GlobSetBuilder::new();
+        let mut exclude_builder = GlobSetBuilder::new();
+        let mut rejected = Vec::new();
+
+        for pattern in patterns {
+            let (builder, glob_str) = match pattern.strip_prefix('!') {
+                Some(rest) => (&mut exclude_builder, rest),
+                None => (&mut include_builder, pattern.as_str()),
+            };
+
+            match Glob::new(glob_str) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(why) => rejected.push(format!("{pattern:?}: {why}")),
+            }
+        }
+
+        let filter = Self {
+            include: include_builder.build().unwrap_or_else(|_| GlobSet::empty()),
+            exclude: exclude_builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        };
+
+        (filter, rejected)
+    }
+
+    /// Whether `path` should be swept into the generated config.
+    fn is_match(&self, path: &Path) -> bool {
+        self.include.is_match(path) && !self.exclude.is_match(path)
+    }
+
+    /// Whether this filter has no include patterns, and so would match
+    /// nothing no matter what is generated against it.
+    fn matches_nothing(&self) -> bool {
+        self.include.is_empty()
+    }
+}
+
+/// The subset of app state that is persisted across sessions via eframe's
+/// storage so users don't have to re-browse for the same folders every
+/// launch.
+#[derive(Serialize, Deserialize)]
+struct AppConfig {
     patch_folder: String,
     patch_output_folder: String,
-    state_message: String,
-    worker_rx: Option<Receiver<MessageToGUI>>,
-    needs_repaint: bool,
+    watch_enabled: bool,
+    #[serde(default = "default_glob_patterns")]
+    glob_patterns: Vec<String>,
 }
 
-impl PatchConfigApp {
-    pub fn new() -> Self {
+fn default_glob_patterns() -> Vec<String> {
+    DEFAULT_GLOB_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
         Self {
             patch_folder: String::default(),
             patch_output_folder: String::default(),
+            watch_enabled: false,
+            glob_patterns: default_glob_patterns(),
+        }
+    }
+}
+
+struct PatchConfigApp {
+    config: AppConfig,
+    state_message: String,
+    job_queue: JobQueue,
+    patch_folder_dialog: Option<PendingFolderDialog>,
+    patch_output_folder_dialog: Option<PendingFolderDialog>,
+    watcher: Option<FileWatcher>,
+    /// Set when a watcher-triggered regeneration couldn't start because a job
+    /// for the same output was already running, so it's retried once that
+    /// job finishes instead of silently dropping the edit that caused it.
+    watch_dirty: bool,
+    entries: Vec<PatchEntry>,
+    object_search: String,
+    only_changed: bool,
+    hide_incomplete: bool,
+    new_glob_pattern: String,
+}
+
+impl PatchConfigApp {
+    /// Restores persisted folders and settings from `cc`'s storage, if any
+    /// was saved by a previous session.
+    pub fn new(cc: &eframe::CreationContext) -> Self {
+        let config: AppConfig = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        let entries = Self::discover_entries(&config.patch_folder);
+
+        Self {
+            config,
             state_message: String::default(),
-            worker_rx: None,
-            needs_repaint: false,
+            job_queue: JobQueue::default(),
+            patch_folder_dialog: None,
+            patch_output_folder_dialog: None,
+            watcher: None,
+            watch_dirty: false,
+            entries,
+            object_search: String::default(),
+            only_changed: false,
+            hide_incomplete: false,
+            new_glob_pattern: String::default(),
         }
     }
 
-    /// Starts a new thread to process a config generation task. Only one may
-    /// be running at a given time.
-    fn start_config_worker(&mut self, input_dir: &Path, output_dir: &Path) {
-        // Do nothing if a worker is already processing data
-        if self.worker_rx.is_some() {
+    /// Walks `patch_folder` and returns the entries `list_patch_entries`
+    /// finds there. The include/exclude glob patterns are not applied, so the
+    /// list can hold files that generation skips. Returns an empty list if no
+    /// folder has been chosen yet, and logs and returns an empty list if the
+    /// folder can't be read.
+    fn discover_entries(patch_folder: &str) -> Vec<PatchEntry> {
+        if patch_folder.is_empty() {
+            return Vec::new();
+        }
+
+        match list_patch_entries(Path::new(patch_folder)) {
+            Ok(entries) => entries,
+            Err(why) => {
+                eprintln!("Could not list Patch Folder contents: {why}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Re-walks the current Patch Folder and refreshes the preview list.
+    fn refresh_entries(&mut self) {
+        self.entries = Self::discover_entries(&self.config.patch_folder);
+    }
+
+    /// Returns the entries that match the current search text and filter
+    /// checkboxes, for display in the "Patch Contents" list.
+    fn filtered_entries(&self) -> Vec<&PatchEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry
+                    .path
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(&self.object_search.to_lowercase())
+            })
+            .filter(|entry| !self.only_changed || entry.changed)
+            .filter(|entry| !self.hide_incomplete || entry.complete)
+            .collect()
+    }
+
+    /// Starts a config generation job using the currently configured
+    /// folders.
+    fn queue_generate(&mut self, ctx: &egui::Context) -> QueueOutcome {
+        let mut output_dir = PathBuf::new();
+        output_dir.push(&self.config.patch_output_folder);
+        output_dir.push("aeco-patch");
+
+        let input_dir = PathBuf::from(&self.config.patch_folder);
+        self.start_config_worker(ctx, &input_dir, &output_dir)
+    }
+
+    /// Keeps the filesystem watcher in sync with the "Watch" checkbox and the
+    /// selected Patch Folder, and kicks off a regeneration whenever the
+    /// watcher reports a debounced change (retrying on later frames if one
+    /// was already in flight for this output, rather than dropping it).
+    fn update_watcher(&mut self, ctx: &egui::Context) {
+        if !self.config.watch_enabled {
+            self.watcher = None;
+            return;
+        }
+
+        // Only (re)build the watcher once the Patch Folder text actually
+        // names a directory that exists. While the user is mid-keystroke
+        // editing the path by hand, `patch_folder` mutates on every frame;
+        // treating every mutation as "the folder changed" would otherwise
+        // attempt (and fail) to watch a partial path on every frame. Leave
+        // the previous watcher (if any) running until a valid folder shows
+        // up rather than tearing it down for a transient, unusable path.
+        let folder = self.config.patch_folder.clone();
+        if !Path::new(&folder).is_dir() {
             return;
         }
 
-        self.set_message("Working...");
+        let needs_rebuild = match &self.watcher {
+            Some(watcher) => watcher.watched_folder != folder,
+            None => true,
+        };
+
+        if needs_rebuild {
+            match FileWatcher::new(&folder, ctx.clone()) {
+                Ok(watcher) => self.watcher = Some(watcher),
+                Err(why) => {
+                    self.set_message(&format!("Could not watch Patch Folder: {why}"));
+                    self.watcher = None;
+                    return;
+                }
+            }
+        }
+
+        let changed = self
+            .watcher
+            .as_ref()
+            .map(FileWatcher::poll_changed)
+            .unwrap_or(false);
+
+        if changed {
+            // The preview list is otherwise only refreshed when the user
+            // browses to a new Patch Folder, so without this a watch-driven
+            // edit would regenerate the output while "Patch Contents" kept
+            // showing stale pre-edit entries.
+            self.refresh_entries();
+            self.watch_dirty = true;
+        }
 
-        let (tx_gui, rx_gui) = channel::<MessageToGUI>();
+        if self.watch_dirty {
+            match self.queue_generate(ctx) {
+                // A job is already running for this output; keep the dirty
+                // flag set and try again on a later frame once it finishes,
+                // instead of losing the edit that triggered it.
+                QueueOutcome::Busy => {}
+                QueueOutcome::Started | QueueOutcome::Rejected => self.watch_dirty = false,
+            }
+        }
+    }
 
-        // Keep the rx side of the channel to receive an update once the task
-        // is finished
-        self.worker_rx = Some(rx_gui);
+    /// Starts a new thread to process a config generation task and registers
+    /// it with the [`JobQueue`] so its progress can be tracked and it can be
+    /// cancelled.
+    fn start_config_worker(
+        &mut self,
+        ctx: &egui::Context,
+        input_dir: &Path,
+        output_dir: &Path,
+    ) -> QueueOutcome {
+        // Never race two workers writing into the same output directory —
+        // a manual Generate click or another debounced watch event could
+        // otherwise pile onto a job that's still running.
+        if self.job_queue.is_running_for(output_dir) {
+            self.set_message("Generation is already in progress for this output folder.");
+            return QueueOutcome::Busy;
+        }
+
+        let (filter, rejected_patterns) = PatchFileFilter::compile(&self.config.glob_patterns);
+        if !rejected_patterns.is_empty() {
+            self.set_message(&format!(
+                "Ignoring invalid glob pattern(s): {}",
+                rejected_patterns.join("; ")
+            ));
+        }
+
+        if filter.matches_nothing() {
+            self.set_message("No include patterns are configured, so nothing would be generated.");
+            return QueueOutcome::Rejected;
+        }
 
         // Convert to Paths so the contents can be owned by the new thread
         let input_dir = input_dir.to_path_buf();
         let output_dir = output_dir.to_path_buf();
 
+        let (tx_gui, cancel) = self.job_queue.start_job(output_dir.clone());
+
+        // egui::Context is internally an Arc, so cloning it is cheap and lets
+        // the worker wake the GUI the moment it has something to report.
+        let ctx = ctx.clone();
+
         // Generate the configuration on a new thread
         thread::spawn(move || {
-            let result = generate_config(input_dir, output_dir);
+            let progress_tx = tx_gui.clone();
+            let progress_ctx = ctx.clone();
+            let on_progress = move |fraction: f32, message: &str| {
+                let _ = progress_tx.send(MessageToGUI::Progress {
+                    fraction,
+                    message: message.to_string(),
+                });
+                progress_ctx.request_repaint();
+            };
+
+            let result = generate_config(
+                input_dir,
+                output_dir,
+                on_progress,
+                cancel.clone(),
+                |path: &Path| filter.is_match(path),
+            );
 
             // Send a response to the GUI depending on what the result of the
             // operation was
-            let message = match result {
-                Ok(_) => MessageToGUI::Complete,
-                Err(why) => MessageToGUI::Error(why),
+            let message = if cancel.load(Ordering::Relaxed) {
+                MessageToGUI::Cancelled
+            } else {
+                match result {
+                    Ok(_) => MessageToGUI::Complete,
+                    Err(why) => MessageToGUI::Error(why),
+                }
             };
 
             if let Err(why) = tx_gui.send(message) {
                 eprintln!("Could not send worker response back to GUI: {why}");
             }
-        });
-    }
 
-    /// If a config worker is running, check on its status and update the GUI
-    /// if it has finished.
-    fn check_config_worker(&mut self) {
-        // Only check if a worker rx channel has been created
-        if let Some(rx) = &self.worker_rx {
-            let message = match rx.try_recv() {
-                Ok(message) => message,
-                Err(err) => match err {
-                    mpsc::TryRecvError::Empty => return,
-                    mpsc::TryRecvError::Disconnected => {
-                        eprintln!("The worker channel has disconnected.");
-                        return;
-                    }
-                },
-            };
-
-            // Provide feedback to the user depending on the result of the
-            // operation
-            match message {
-                MessageToGUI::Complete => {
-                    self.set_message("Finished!");
-                }
-                MessageToGUI::Error(why) => {
-                    self.set_message(&format!("Failled to generate output: {}", why.to_string()));
-                }
-            }
+            ctx.request_repaint();
+        });
 
-            // Remove this end of the worker channel so new workers can be
-            // created
-            self.worker_rx = None;
-        }
+        QueueOutcome::Started
     }
 
     /// Sets the status message which is displayed to the user
     pub fn set_message(&mut self, message: &str) {
         self.state_message = message.to_string();
-        self.needs_repaint = true;
     }
 
-    fn generate_button(&mut self, ui: &mut egui::Ui) {
-        if ui.button("Generate").clicked() {
-            // Only start a config generation task if one is not already
-            // running
-            if self.worker_rx.is_none() {
-                let mut output_dir = PathBuf::new();
-                output_dir.push(&self.patch_output_folder);
-                output_dir.push("aeco-patch");
-
-                let input_dir = PathBuf::from(&self.patch_folder);
-                self.start_config_worker(&input_dir, &output_dir);
-            } else {
-                self.set_message("Generation already in progress.")
+    /// Polls any in-flight folder picker dialogs and applies their result to
+    /// the relevant text field once they complete.
+    fn check_folder_dialogs(&mut self) {
+        if let Some(dialog) = &self.patch_folder_dialog {
+            if let Some(picked) = dialog.poll() {
+                self.patch_folder_dialog = None;
+                if let Some(path) = picked {
+                    self.apply_picked_folder(&path, Field::PatchFolder);
+                }
             }
         }
-    }
 
-    fn browse_patch_folder_button(&mut self, ui: &mut egui::Ui) {
-        if !ui.button("Browse").clicked() {
-            return;
+        if let Some(dialog) = &self.patch_output_folder_dialog {
+            if let Some(picked) = dialog.poll() {
+                self.patch_output_folder_dialog = None;
+                if let Some(path) = picked {
+                    self.apply_picked_folder(&path, Field::PatchOutputFolder);
+                }
+            }
         }
+    }
 
-        let file_dialog = FileDialog::new();
-        let path = match file_dialog.pick_folder() {
-            Some(x) => x,
-            None => return,
-        };
-
+    fn apply_picked_folder(&mut self, path: &Path, field: Field) {
         let path_str = match path.to_str() {
             Some(x) => x,
             None => {
@@ -144,61 +620,113 @@ impl PatchConfigApp {
             }
         };
 
-        self.patch_folder = path_str.to_string();
+        match field {
+            Field::PatchFolder => {
+                self.config.patch_folder = path_str.to_string();
+                self.refresh_entries();
+            }
+            Field::PatchOutputFolder => self.config.patch_output_folder = path_str.to_string(),
+        }
     }
 
-    fn browse_patch_output_folder_button(&mut self, ui: &mut egui::Ui) {
+    fn generate_button(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        if ui.button("Generate").clicked() {
+            self.queue_generate(ctx);
+        }
+    }
+
+    fn browse_patch_folder_button(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         if !ui.button("Browse").clicked() {
             return;
         }
 
-        let file_dialog = FileDialog::new();
-        let path = match file_dialog.pick_folder() {
-            Some(x) => x,
-            None => return,
-        };
+        // Ignore the click if a dialog for this field is already open
+        if self.patch_folder_dialog.is_none() {
+            self.patch_folder_dialog = Some(PendingFolderDialog::spawn(ctx.clone()));
+        }
+    }
 
-        let path_str = match path.to_str() {
-            Some(x) => x,
-            None => {
-                self.set_message("Selected path could not be converted to a string.");
-                return;
-            }
-        };
+    fn browse_patch_output_folder_button(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        if !ui.button("Browse").clicked() {
+            return;
+        }
+
+        if self.patch_output_folder_dialog.is_none() {
+            self.patch_output_folder_dialog = Some(PendingFolderDialog::spawn(ctx.clone()));
+        }
+    }
+
+    /// Renders the glob pattern list (with delete buttons) and the text
+    /// field used to add new patterns.
+    fn glob_pattern_editor(&mut self, ui: &mut egui::Ui) {
+        ui.label("Include/Exclude Patterns");
+
+        let mut removed = None;
+        for (index, pattern) in self.config.glob_patterns.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(pattern);
+                if ui.button("X").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            self.config.glob_patterns.remove(index);
+        }
 
-        self.patch_output_folder = path_str.to_string();
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_glob_pattern);
+            if ui.button("Add Pattern").clicked() && !self.new_glob_pattern.is_empty() {
+                self.config
+                    .glob_patterns
+                    .push(std::mem::take(&mut self.new_glob_pattern));
+            }
+        });
     }
 }
 
+/// Identifies which text field a picked folder path should be written into.
+enum Field {
+    PatchFolder,
+    PatchOutputFolder,
+}
+
 impl eframe::App for PatchConfigApp {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
-        self.check_config_worker();
-
-        if self.needs_repaint {
-            ctx.request_repaint();
-            self.needs_repaint = false;
-        }
+        self.job_queue.poll();
+        self.check_folder_dialogs();
+        self.update_watcher(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::TopBottomPanel::top("top-panel").show_inside(ui, |ui| {
                 egui::SidePanel::right("generate-panel")
                     .frame(egui::Frame::none())
                     .show_inside(ui, |ui| {
-                        ui.centered_and_justified(|ui| {
-                            self.generate_button(ui);
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.config.watch_enabled, "Watch");
+                            self.generate_button(ctx, ui);
                         });
                     });
                 ui.label("Patch Folder");
                 ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut self.patch_folder);
-                    self.browse_patch_folder_button(ui);
+                    ui.text_edit_singleline(&mut self.config.patch_folder);
+                    self.browse_patch_folder_button(ctx, ui);
                 });
 
                 ui.label("Patch Output Folder");
                 ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut self.patch_output_folder);
-                    self.browse_patch_output_folder_button(ui);
+                    ui.text_edit_singleline(&mut self.config.patch_output_folder);
+                    self.browse_patch_output_folder_button(ctx, ui);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+                    ui.text_edit_singleline(&mut self.object_search);
+                    ui.checkbox(&mut self.only_changed, "Only changed");
+                    ui.checkbox(&mut self.hide_incomplete, "Hide incomplete");
                 });
+
+                self.glob_pattern_editor(ui);
             });
             egui::TopBottomPanel::top("message-panel").show_inside(ui, |ui| {
                 ui.horizontal_centered(|ui| {
@@ -208,15 +736,56 @@ impl eframe::App for PatchConfigApp {
                 });
             });
 
+            let entries = self.filtered_entries();
+            ui.label("Patch Contents");
             egui::ScrollArea::vertical()
+                .id_source("patch-contents")
+                .max_height(200.)
+                .auto_shrink([false, true])
+                .show_rows(ui, 14., entries.len(), |ui, row_range| {
+                    for row in row_range {
+                        let entry = entries[row];
+                        ui.horizontal(|ui| {
+                            ui.label(entry.path.to_string_lossy());
+                            ui.label(format!("{} bytes", entry.size));
+                            if entry.changed {
+                                ui.label("changed");
+                            }
+                            if !entry.complete {
+                                ui.label("incomplete");
+                            }
+                        });
+                    }
+                });
+
+            ui.separator();
+
+            let jobs = &self.job_queue.jobs;
+            egui::ScrollArea::vertical()
+                .id_source("job-queue")
                 .auto_shrink([false; 2])
-                .show_rows(ui, 14., 50, |ui, row_range| {
+                .show_rows(ui, 40., jobs.len(), |ui, row_range| {
                     for row in row_range {
-                        // ui.label("hello");
+                        let job = &jobs[row];
+
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Job #{}: {}", job.id, job.status));
+
+                                if !job.finished && ui.button("Cancel").clicked() {
+                                    job.cancel.store(true, Ordering::Relaxed);
+                                }
+                            });
+                            ui.add(egui::ProgressBar::new(job.progress).show_percentage());
+                        });
                     }
                 });
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.config);
+    }
 }
 
 fn main() {
@@ -230,6 +799,6 @@ fn main() {
             resizable: false,
             ..eframe::NativeOptions::default()
         },
-        Box::new(|_cc| Box::new(PatchConfigApp::new())),
+        Box::new(|cc| Box::new(PatchConfigApp::new(cc))),
     );
-}
\ No newline at end of file
+}